@@ -1,4 +1,5 @@
 use wasm_bindgen::prelude::*;
+use js_sys::Function;
 use std::collections::HashMap;
 use std::cell::RefCell;
 
@@ -30,6 +31,95 @@ pub struct ProcessControlBlock {
     exit_code: i32,
 }
 
+// ============================================================================
+// FILESYSTEM ERRORS
+// ============================================================================
+
+/// Structured filesystem error, mapped to a negative POSIX errno in the FUSE
+/// style used by the mount crates, so callers can distinguish failure modes
+/// instead of collapsing everything into `-1`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FsError {
+    NotFound,
+    InodeNotFound,
+    NotADirectory,
+    IsDirectory,
+    InvalidPath,
+    UnsupportedOperation,
+    Recursion,
+    /// A snapshot blob failed validation on `fs_restore`.
+    Corrupt,
+    /// `O_CREAT | O_EXCL` on a path that already exists.
+    AlreadyExists,
+    /// A `truncate`/`write` length or offset would grow a file past `MAX_FILE_SIZE`.
+    TooLarge,
+}
+
+pub const ENOENT: i32 = -2;
+pub const EEXIST: i32 = -17;
+pub const EBADF: i32 = -9;
+pub const ENOTDIR: i32 = -20;
+pub const EISDIR: i32 = -21;
+pub const EINVAL: i32 = -22;
+pub const EFBIG: i32 = -27;
+pub const ELOOP: i32 = -40;
+pub const ENOSYS: i32 = -38;
+
+impl FsError {
+    fn errno(&self) -> i32 {
+        match self {
+            FsError::NotFound => ENOENT,
+            FsError::InodeNotFound => EBADF,
+            FsError::NotADirectory => ENOTDIR,
+            FsError::IsDirectory => EISDIR,
+            FsError::InvalidPath => EINVAL,
+            FsError::UnsupportedOperation => ENOSYS,
+            FsError::Recursion => ELOOP,
+            FsError::Corrupt => EINVAL,
+            FsError::AlreadyExists => EEXIST,
+            FsError::TooLarge => EFBIG,
+        }
+    }
+
+    fn message(&self) -> &'static str {
+        match self {
+            FsError::NotFound => "No such file or directory",
+            FsError::InodeNotFound => "Bad file descriptor",
+            FsError::NotADirectory => "Not a directory",
+            FsError::IsDirectory => "Is a directory",
+            FsError::InvalidPath => "Invalid argument",
+            FsError::UnsupportedOperation => "Function not implemented",
+            FsError::Recursion => "Too many levels of symbolic links",
+            FsError::Corrupt => "Corrupt snapshot data",
+            FsError::AlreadyExists => "File exists",
+            FsError::TooLarge => "File too large",
+        }
+    }
+}
+
+/// Render the message for a negative errno previously returned by an `fs_*`
+/// syscall, for shells to print as a diagnostic.
+#[wasm_bindgen]
+pub fn fs_strerror(code: i32) -> String {
+    for err in [
+        FsError::NotFound,
+        FsError::InodeNotFound,
+        FsError::NotADirectory,
+        FsError::IsDirectory,
+        FsError::InvalidPath,
+        FsError::UnsupportedOperation,
+        FsError::Recursion,
+        FsError::Corrupt,
+        FsError::AlreadyExists,
+        FsError::TooLarge,
+    ] {
+        if err.errno() == code {
+            return err.message().to_string();
+        }
+    }
+    "Unknown error".to_string()
+}
+
 // ============================================================================
 // VIRTUAL FILE SYSTEM
 // ============================================================================
@@ -38,6 +128,184 @@ pub struct ProcessControlBlock {
 pub enum InodeType {
     File,
     Directory,
+    /// `data` holds the UTF-8 encoded target path, as in the WASI/fossil VFS
+    /// layers.
+    Symlink,
+    /// A byte-stream device node, mirroring the character-special file kind
+    /// in the WASI/fossil mount layers. `data` holds the 4-byte
+    /// little-endian `device_id` used to look up the host callback in
+    /// `Kernel::device_handlers`.
+    CharDevice,
+    /// A block-addressable device node, mirroring the block-special file
+    /// kind in the same layers. Dispatches through the same device-handler
+    /// registry as `CharDevice`.
+    BlockDevice,
+}
+
+/// Safety cap on total symlink expansions per lookup, to turn a symlink
+/// cycle into an error instead of an infinite loop.
+const MAX_SYMLINK_EXPANSIONS: u32 = 40;
+
+/// Upper bound on a single file's size. `truncate`/`write` reject anything
+/// past this instead of resizing unconditionally, so a bogus caller-supplied
+/// length/offset can't make the kernel allocate and zero a multi-gigabyte
+/// buffer, which would blow past the wasm32 linear-memory limit and trap.
+const MAX_FILE_SIZE: usize = 64 * 1024 * 1024;
+
+// ============================================================================
+// OPEN FLAGS & SEEK WHENCE
+// ============================================================================
+//
+// `fs_open`'s `flags` argument follows the same bit layout as POSIX's
+// `open(2)`, so callers in the shell can reuse the constants they already
+// know instead of learning a bespoke mode string.
+
+pub const O_RDONLY: u32 = 0o0;
+pub const O_WRONLY: u32 = 0o1;
+pub const O_RDWR: u32 = 0o2;
+pub const O_CREAT: u32 = 0o100;
+pub const O_EXCL: u32 = 0o200;
+pub const O_TRUNC: u32 = 0o1000;
+pub const O_APPEND: u32 = 0o2000;
+
+/// `whence` values for `fs_seek`, matching POSIX `lseek(2)`.
+pub const SEEK_SET: u32 = 0;
+pub const SEEK_CUR: u32 = 1;
+pub const SEEK_END: u32 = 2;
+
+// ============================================================================
+// DEVICE NODES
+// ============================================================================
+
+fn device_id_bytes(device_id: u32) -> Vec<u8> {
+    device_id.to_le_bytes().to_vec()
+}
+
+fn device_id_from_data(data: &[u8]) -> Result<u32, FsError> {
+    let bytes: [u8; 4] = data.try_into().map_err(|_| FsError::Corrupt)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+/// Walk `path` from `root_inode_id` through `inode_map`, substituting
+/// symlink targets as they're encountered (absolute targets restart from
+/// root, relative targets resolve against the symlink's parent) until a
+/// non-symlink inode is reached. When `follow_final` is false, a symlink as
+/// the last path component is returned as-is instead of being followed
+/// (lstat-style).
+///
+/// Used by `MemoryDevice::get_inode`, which has no mount table of its own.
+/// `Kernel` resolves through `resolve_in_root` instead, which is the same
+/// walk but re-checks the mount table when a symlink target crosses into a
+/// mounted subtree.
+fn resolve_in_tree(inode_map: &HashMap<u32, Inode>, root_inode_id: u32, path: &str, follow_final: bool) -> Result<u32, FsError> {
+    let trimmed = path.trim_matches('/');
+    if trimmed.is_empty() {
+        return Ok(root_inode_id);
+    }
+
+    let mut remaining: Vec<String> = trimmed.split('/').filter(|c| !c.is_empty()).map(|s| s.to_string()).collect();
+    remaining.reverse();
+
+    let mut current_id = root_inode_id;
+    let mut expansions = 0u32;
+
+    while let Some(component) = remaining.pop() {
+        let inode = inode_map.get(&current_id).ok_or(FsError::InodeNotFound)?;
+        let next_id = *inode.children.get(&component).ok_or(FsError::NotFound)?;
+        let next_inode = inode_map.get(&next_id).ok_or(FsError::InodeNotFound)?;
+        let is_last = remaining.is_empty();
+
+        if matches!(next_inode.inode_type, InodeType::Symlink) && (!is_last || follow_final) {
+            expansions += 1;
+            if expansions > MAX_SYMLINK_EXPANSIONS {
+                return Err(FsError::Recursion);
+            }
+
+            let target = String::from_utf8_lossy(&next_inode.data).to_string();
+            let is_absolute = target.starts_with('/');
+            let mut target_components: Vec<String> = target.trim_matches('/').split('/')
+                .filter(|c| !c.is_empty())
+                .map(|s| s.to_string())
+                .collect();
+            remaining.extend(target_components.drain(..).rev());
+
+            if is_absolute {
+                current_id = root_inode_id;
+            }
+            // Relative targets keep resolving from current_id, the symlink's parent.
+        } else {
+            current_id = next_id;
+        }
+    }
+
+    Ok(current_id)
+}
+
+// ============================================================================
+// SNAPSHOT ENCODING
+// ============================================================================
+//
+// A simple length-prefixed binary format for persisting the inode tree
+// across reloads: next_inode_id, root_inode_id, inode count, then per
+// inode: id, type tag, name, parent (u32::MAX for none), data bytes, and
+// child count followed by name/id pairs.
+
+/// Sentinel parent id meaning "no parent" (the root inode).
+const NO_PARENT: u32 = u32::MAX;
+
+fn inode_type_tag(inode_type: &InodeType) -> u8 {
+    match inode_type {
+        InodeType::File => 0,
+        InodeType::Directory => 1,
+        InodeType::Symlink => 2,
+        InodeType::CharDevice => 3,
+        InodeType::BlockDevice => 4,
+    }
+}
+
+fn inode_type_from_tag(tag: u8) -> Result<InodeType, FsError> {
+    match tag {
+        0 => Ok(InodeType::File),
+        1 => Ok(InodeType::Directory),
+        2 => Ok(InodeType::Symlink),
+        3 => Ok(InodeType::CharDevice),
+        4 => Ok(InodeType::BlockDevice),
+        _ => Err(FsError::Corrupt),
+    }
+}
+
+fn write_u32(buf: &mut Vec<u8>, value: u32) {
+    buf.extend_from_slice(&value.to_le_bytes());
+}
+
+fn write_bytes(buf: &mut Vec<u8>, bytes: &[u8]) {
+    write_u32(buf, bytes.len() as u32);
+    buf.extend_from_slice(bytes);
+}
+
+fn read_u32(buf: &[u8], pos: &mut usize) -> Result<u32, FsError> {
+    let end = pos.checked_add(4).ok_or(FsError::Corrupt)?;
+    let bytes: [u8; 4] = buf.get(*pos..end).ok_or(FsError::Corrupt)?.try_into().map_err(|_| FsError::Corrupt)?;
+    *pos = end;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u8(buf: &[u8], pos: &mut usize) -> Result<u8, FsError> {
+    let byte = *buf.get(*pos).ok_or(FsError::Corrupt)?;
+    *pos += 1;
+    Ok(byte)
+}
+
+fn read_bytes(buf: &[u8], pos: &mut usize) -> Result<Vec<u8>, FsError> {
+    let len = read_u32(buf, pos)? as usize;
+    let end = pos.checked_add(len).ok_or(FsError::Corrupt)?;
+    let bytes = buf.get(*pos..end).ok_or(FsError::Corrupt)?.to_vec();
+    *pos = end;
+    Ok(bytes)
+}
+
+fn read_string(buf: &[u8], pos: &mut usize) -> Result<String, FsError> {
+    String::from_utf8(read_bytes(buf, pos)?).map_err(|_| FsError::Corrupt)
 }
 
 #[derive(Clone)]
@@ -50,11 +318,189 @@ pub struct Inode {
     pub parent: Option<u32>,
 }
 
+/// Where a resolved path lives: in the kernel's own inode tree, or behind a
+/// mounted `StorageDevice`.
+enum Resolved {
+    Root(u32),
+    Mounted { mount_point: String, subpath: String },
+}
+
+#[derive(Clone)]
+pub enum FdTarget {
+    Root(u32),
+    Mounted { mount_point: String, path: String },
+}
+
 #[derive(Clone)]
 pub struct FileDescriptor {
-    pub inode_id: u32,
+    pub target: FdTarget,
     pub offset: usize,
-    pub mode: String,  // "r" or "w"
+    /// The `O_*` flags the fd was opened with; `write_file` consults
+    /// `O_APPEND` to force writes to the current end of the file.
+    pub flags: u32,
+}
+
+/// Result of a `StorageDevice::stat` call.
+pub struct DeviceStat {
+    pub is_dir: bool,
+    pub size: usize,
+}
+
+/// A backend that can be mounted at a path in the VFS, following the ableos
+/// design of pushing filesystem operations behind a trait object so the
+/// kernel can host more than one backend at a time.
+pub trait StorageDevice {
+    fn open(&self, path: &str) -> Result<(), FsError>;
+    fn read(&self, path: &str, offset: usize, size: usize) -> Result<Vec<u8>, FsError>;
+    fn write(&mut self, path: &str, offset: usize, data: &[u8]) -> Result<usize, FsError>;
+    fn create(&mut self, path: &str, is_dir: bool) -> Result<(), FsError>;
+    fn truncate(&mut self, path: &str, len: usize) -> Result<(), FsError>;
+    fn list(&self, path: &str) -> Result<Vec<String>, FsError>;
+    fn stat(&self, path: &str) -> Result<DeviceStat, FsError>;
+}
+
+/// An in-memory backend, structurally identical to the kernel's own root
+/// tree. Used for `fs_mount`ed devices; `read_only` backs things like a
+/// bundled image mounted under `/bin`.
+pub struct MemoryDevice {
+    read_only: bool,
+    inode_map: HashMap<u32, Inode>,
+    next_inode_id: u32,
+    root_inode_id: u32,
+}
+
+impl MemoryDevice {
+    fn new(read_only: bool) -> Self {
+        let mut inode_map = HashMap::new();
+        inode_map.insert(0, Inode {
+            id: 0,
+            inode_type: InodeType::Directory,
+            name: "/".to_string(),
+            data: vec![],
+            children: HashMap::new(),
+            parent: None,
+        });
+
+        MemoryDevice {
+            read_only,
+            inode_map,
+            next_inode_id: 1,
+            root_inode_id: 0,
+        }
+    }
+
+    fn get_inode(&self, path: &str) -> Result<u32, FsError> {
+        resolve_in_tree(&self.inode_map, self.root_inode_id, path, true)
+    }
+}
+
+impl StorageDevice for MemoryDevice {
+    fn open(&self, path: &str) -> Result<(), FsError> {
+        self.get_inode(path).map(|_| ())
+    }
+
+    fn read(&self, path: &str, offset: usize, size: usize) -> Result<Vec<u8>, FsError> {
+        let id = self.get_inode(path)?;
+        let inode = self.inode_map.get(&id).ok_or(FsError::InodeNotFound)?;
+        let start = std::cmp::min(offset, inode.data.len());
+        let end = std::cmp::min(start + size, inode.data.len());
+        Ok(inode.data[start..end].to_vec())
+    }
+
+    fn write(&mut self, path: &str, offset: usize, data: &[u8]) -> Result<usize, FsError> {
+        if self.read_only {
+            return Err(FsError::UnsupportedOperation);
+        }
+
+        let id = self.get_inode(path)?;
+        let inode = self.inode_map.get_mut(&id).ok_or(FsError::InodeNotFound)?;
+        if matches!(inode.inode_type, InodeType::Directory) {
+            return Err(FsError::IsDirectory);
+        }
+
+        let end = offset.checked_add(data.len()).filter(|&end| end <= MAX_FILE_SIZE).ok_or(FsError::TooLarge)?;
+        if inode.data.len() < end {
+            inode.data.resize(end, 0);
+        }
+        inode.data[offset..end].copy_from_slice(data);
+        Ok(data.len())
+    }
+
+    fn create(&mut self, path: &str, is_dir: bool) -> Result<(), FsError> {
+        if self.read_only {
+            return Err(FsError::UnsupportedOperation);
+        }
+
+        let path = path.trim_matches('/');
+        if path.is_empty() {
+            return Err(FsError::InvalidPath);
+        }
+
+        let (parent_path, name) = match path.rfind('/') {
+            Some(i) => (&path[..i], path[i + 1..].to_string()),
+            None => ("", path.to_string()),
+        };
+        if name.is_empty() {
+            return Err(FsError::InvalidPath);
+        }
+
+        let parent_id = self.get_inode(parent_path)?;
+        let inode_id = self.next_inode_id;
+        self.next_inode_id += 1;
+
+        let inode_type = if is_dir { InodeType::Directory } else { InodeType::File };
+        let inode = Inode {
+            id: inode_id,
+            inode_type,
+            name: name.clone(),
+            data: vec![],
+            children: HashMap::new(),
+            parent: Some(parent_id),
+        };
+        self.inode_map.insert(inode_id, inode);
+
+        let parent = self.inode_map.get_mut(&parent_id).ok_or(FsError::InodeNotFound)?;
+        if !matches!(parent.inode_type, InodeType::Directory) {
+            return Err(FsError::NotADirectory);
+        }
+        parent.children.insert(name, inode_id);
+        Ok(())
+    }
+
+    fn truncate(&mut self, path: &str, len: usize) -> Result<(), FsError> {
+        if self.read_only {
+            return Err(FsError::UnsupportedOperation);
+        }
+
+        let id = self.get_inode(path)?;
+        let inode = self.inode_map.get_mut(&id).ok_or(FsError::InodeNotFound)?;
+        if matches!(inode.inode_type, InodeType::Directory) {
+            return Err(FsError::IsDirectory);
+        }
+        if len > MAX_FILE_SIZE {
+            return Err(FsError::TooLarge);
+        }
+        inode.data.resize(len, 0);
+        Ok(())
+    }
+
+    fn list(&self, path: &str) -> Result<Vec<String>, FsError> {
+        let id = self.get_inode(path)?;
+        let inode = self.inode_map.get(&id).ok_or(FsError::InodeNotFound)?;
+        if !matches!(inode.inode_type, InodeType::Directory) {
+            return Err(FsError::NotADirectory);
+        }
+        Ok(inode.children.keys().cloned().collect())
+    }
+
+    fn stat(&self, path: &str) -> Result<DeviceStat, FsError> {
+        let id = self.get_inode(path)?;
+        let inode = self.inode_map.get(&id).ok_or(FsError::InodeNotFound)?;
+        Ok(DeviceStat {
+            is_dir: matches!(inode.inode_type, InodeType::Directory),
+            size: inode.data.len(),
+        })
+    }
 }
 
 // ============================================================================
@@ -65,18 +511,24 @@ pub struct Kernel {
     booted: bool,
     start_time_ms: u64,
     current_time_ms: u64,
-    
+
     // Process management
     process_table: HashMap<u32, ProcessControlBlock>,
     next_pid: u32,
     current_pid: u32,
-    
+
     // Virtual file system
     inode_map: HashMap<u32, Inode>,
     next_inode_id: u32,
     root_inode_id: u32,
+    mount_table: HashMap<String, Box<dyn StorageDevice>>,
     open_files: HashMap<u32, FileDescriptor>,
     next_fd: u32,
+
+    /// Host-supplied callbacks for `CharDevice`/`BlockDevice` inodes, keyed
+    /// by the `device_id` stored in the inode, e.g. `/dev/random` backed by
+    /// `crypto.getRandomValues` or `/dev/console` forwarding to the terminal.
+    device_handlers: HashMap<u32, Function>,
 }
 
 impl Kernel {
@@ -91,10 +543,12 @@ impl Kernel {
             inode_map: HashMap::new(),
             next_inode_id: 1,
             root_inode_id: 0,
+            mount_table: HashMap::new(),
             open_files: HashMap::new(),
             next_fd: 3,  // 0, 1, 2 are stdin, stdout, stderr
+            device_handlers: HashMap::new(),
         };
-        
+
         // Initialize filesystem with root directory
         let root = Inode {
             id: 0,
@@ -105,7 +559,7 @@ impl Kernel {
             parent: None,
         };
         kernel.inode_map.insert(0, root);
-        
+
         // Create initial process (init)
         let init_pcb = ProcessControlBlock {
             pid: 0,
@@ -116,7 +570,7 @@ impl Kernel {
         kernel.process_table.insert(0, init_pcb);
         kernel.next_pid = 1;
         kernel.current_pid = 0;
-        
+
         kernel
     }
 
@@ -127,14 +581,14 @@ impl Kernel {
     fn create_process(&mut self, parent_pid: u32) -> u32 {
         let pid = self.next_pid;
         self.next_pid += 1;
-        
+
         let pcb = ProcessControlBlock {
             pid,
             state: ProcessState::Ready,
             parent_pid: Some(parent_pid),
             exit_code: 0,
         };
-        
+
         self.process_table.insert(pid, pcb);
         pid
     }
@@ -151,10 +605,10 @@ impl Kernel {
     // FILE SYSTEM METHODS
     // ========================================================================
 
-    fn create_inode(&mut self, parent_id: u32, name: String, inode_type: InodeType) -> Result<u32, String> {
+    fn create_inode(&mut self, parent_id: u32, name: String, inode_type: InodeType) -> Result<u32, FsError> {
         let inode_id = self.next_inode_id;
         self.next_inode_id += 1;
-        
+
         let inode = Inode {
             id: inode_id,
             inode_type,
@@ -163,127 +617,565 @@ impl Kernel {
             children: HashMap::new(),
             parent: Some(parent_id),
         };
-        
+
         self.inode_map.insert(inode_id, inode);
-        
+
         // Add to parent directory
         if let Some(parent) = self.inode_map.get_mut(&parent_id) {
             if matches!(parent.inode_type, InodeType::Directory) {
                 parent.children.insert(name, inode_id);
                 Ok(inode_id)
             } else {
-                Err("Parent is not a directory".to_string())
+                Err(FsError::NotADirectory)
             }
         } else {
-            Err("Parent inode not found".to_string())
+            Err(FsError::InodeNotFound)
         }
     }
 
-    fn get_inode(&self, path: &str) -> Result<u32, String> {
-        let path = path.trim_matches('/');
-        if path.is_empty() {
-            return Ok(self.root_inode_id);
+    fn create_symlink(&mut self, parent_id: u32, name: String, target: String) -> Result<u32, FsError> {
+        let inode_id = self.create_inode(parent_id, name, InodeType::Symlink)?;
+        self.inode_map.get_mut(&inode_id).unwrap().data = target.into_bytes();
+        Ok(inode_id)
+    }
+
+    fn mknod(&mut self, parent_id: u32, name: String, is_block: bool, device_id: u32) -> Result<u32, FsError> {
+        let inode_type = if is_block { InodeType::BlockDevice } else { InodeType::CharDevice };
+        let inode_id = self.create_inode(parent_id, name, inode_type)?;
+        self.inode_map.get_mut(&inode_id).unwrap().data = device_id_bytes(device_id);
+        Ok(inode_id)
+    }
+
+    fn register_device_handler(&mut self, device_id: u32, handler: Function) {
+        self.device_handlers.insert(device_id, handler);
+    }
+
+    /// Invoke the registered handler for `device_id` to read up to `size`
+    /// bytes, e.g. `/dev/random` pulling from `crypto.getRandomValues`.
+    fn read_device(&self, device_id: u32, size: usize) -> Result<Vec<u8>, FsError> {
+        let handler = self.device_handlers.get(&device_id).ok_or(FsError::UnsupportedOperation)?;
+        let result = handler.call1(&JsValue::NULL, &JsValue::from(size as u32))
+            .map_err(|_| FsError::UnsupportedOperation)?;
+        let mut bytes = js_sys::Uint8Array::new(&result).to_vec();
+        bytes.truncate(size);
+        Ok(bytes)
+    }
+
+    /// Invoke the registered handler for `device_id` with `data`, e.g.
+    /// `/dev/console` forwarding writes to the terminal.
+    fn write_device(&self, device_id: u32, data: &[u8]) -> Result<usize, FsError> {
+        let handler = self.device_handlers.get(&device_id).ok_or(FsError::UnsupportedOperation)?;
+        handler.call1(&JsValue::NULL, &js_sys::Uint8Array::from(data))
+            .map_err(|_| FsError::UnsupportedOperation)?;
+        Ok(data.len())
+    }
+
+    /// Resolve a path to either an inode in the root tree, or a (mount
+    /// point, subpath) pair when the path crosses a mount point. The mount
+    /// table is checked against every prefix of `path`, longest first, so a
+    /// device mounted at `/bin` shadows the root tree for anything under it.
+    fn resolve(&self, path: &str) -> Result<Resolved, FsError> {
+        self.resolve_with(path, true)
+    }
+
+    /// Like `resolve`, but `follow_final` controls whether a symlink as the
+    /// last path component is followed (lstat-style when false).
+    fn resolve_with(&self, path: &str, follow_final: bool) -> Result<Resolved, FsError> {
+        let trimmed = path.trim_matches('/');
+        let components: Vec<String> = trimmed.split('/').filter(|c| !c.is_empty()).map(|s| s.to_string()).collect();
+
+        if let Some((mount_point, subpath)) = self.match_mount_prefix(&components) {
+            return Ok(Resolved::Mounted { mount_point, subpath });
         }
-        
+
+        self.resolve_in_root(components, follow_final)
+    }
+
+    /// Check `components` against every prefix of the mount table, longest
+    /// first, so a device mounted at `/bin` shadows the root tree for
+    /// anything under it. Returns the matching mount point and the leftover
+    /// subpath to hand to that device.
+    fn match_mount_prefix(&self, components: &[String]) -> Option<(String, String)> {
+        for split in (1..=components.len()).rev() {
+            let mount_point = format!("/{}", components[..split].join("/"));
+            if self.mount_table.contains_key(&mount_point) {
+                let subpath = components[split..].join("/");
+                return Some((mount_point, subpath));
+            }
+        }
+        None
+    }
+
+    /// Walk `components` from the root inode, substituting symlink targets
+    /// as they're encountered (as `resolve_in_tree` does for a plain,
+    /// mount-unaware tree), but re-checking the mount table every time a
+    /// symlink splices in new components. Without this, a symlink whose
+    /// target crosses into a mounted subtree (e.g. `/home/user/link ->
+    /// /bin/ls` with `/bin` mounted) would keep resolving against the
+    /// kernel's own root tree instead of dispatching to the mounted device,
+    /// silently bypassing the mount.
+    fn resolve_in_root(&self, components: Vec<String>, follow_final: bool) -> Result<Resolved, FsError> {
+        if components.is_empty() {
+            return Ok(Resolved::Root(self.root_inode_id));
+        }
+
+        let mut remaining: Vec<String> = components;
+        remaining.reverse();
+
+        let mut prefix: Vec<String> = Vec::new();
         let mut current_id = self.root_inode_id;
-        
-        for component in path.split('/') {
-            if component.is_empty() {
-                continue;
-            }
-            
-            if let Some(inode) = self.inode_map.get(&current_id) {
-                if let Some(&next_id) = inode.children.get(component) {
-                    current_id = next_id;
-                } else {
-                    return Err(format!("Path component not found: {}", component));
+        let mut expansions = 0u32;
+
+        while let Some(component) = remaining.pop() {
+            let inode = self.inode_map.get(&current_id).ok_or(FsError::InodeNotFound)?;
+            let next_id = *inode.children.get(&component).ok_or(FsError::NotFound)?;
+            let next_inode = self.inode_map.get(&next_id).ok_or(FsError::InodeNotFound)?;
+            let is_last = remaining.is_empty();
+
+            if matches!(next_inode.inode_type, InodeType::Symlink) && (!is_last || follow_final) {
+                expansions += 1;
+                if expansions > MAX_SYMLINK_EXPANSIONS {
+                    return Err(FsError::Recursion);
+                }
+
+                let target = String::from_utf8_lossy(&next_inode.data).to_string();
+                let is_absolute = target.starts_with('/');
+                let mut target_components: Vec<String> = target.trim_matches('/').split('/')
+                    .filter(|c| !c.is_empty())
+                    .map(|s| s.to_string())
+                    .collect();
+                remaining.extend(target_components.drain(..).rev());
+
+                if is_absolute {
+                    current_id = self.root_inode_id;
+                    prefix.clear();
+                }
+                // Relative targets keep resolving from current_id, the symlink's
+                // parent, so `prefix` (the path from root to current_id) is unchanged.
+
+                let candidate: Vec<String> = prefix.iter().cloned().chain(remaining.iter().rev().cloned()).collect();
+                if let Some((mount_point, subpath)) = self.match_mount_prefix(&candidate) {
+                    return Ok(Resolved::Mounted { mount_point, subpath });
                 }
             } else {
-                return Err("Inode not found during traversal".to_string());
+                current_id = next_id;
+                prefix.push(component);
+            }
+        }
+
+        Ok(Resolved::Root(current_id))
+    }
+
+    fn mount(&mut self, path: &str, device_kind: &str) -> Result<(), FsError> {
+        let mount_point = format!("/{}", path.trim_matches('/'));
+        if mount_point == "/" {
+            return Err(FsError::InvalidPath);
+        }
+
+        let device: Box<dyn StorageDevice> = match device_kind {
+            "mem" => Box::new(MemoryDevice::new(false)),
+            "readonly" => Box::new(MemoryDevice::new(true)),
+            _ => return Err(FsError::InvalidPath),
+        };
+
+        self.mount_table.insert(mount_point, device);
+        Ok(())
+    }
+
+    fn unmount(&mut self, path: &str) -> Result<(), FsError> {
+        let mount_point = format!("/{}", path.trim_matches('/'));
+        if self.mount_table.remove(&mount_point).is_some() {
+            Ok(())
+        } else {
+            Err(FsError::NotFound)
+        }
+    }
+
+    fn create_at(&mut self, resolved: Resolved, name_hint: &str, is_dir: bool) -> Result<(), FsError> {
+        match resolved {
+            Resolved::Root(parent_id) => {
+                self.create_inode(parent_id, name_hint.to_string(), if is_dir { InodeType::Directory } else { InodeType::File })
+                    .map(|_| ())
+            }
+            Resolved::Mounted { mount_point, subpath } => {
+                let device = self.mount_table.get_mut(&mount_point).ok_or(FsError::NotFound)?;
+                let full_path = if subpath.is_empty() {
+                    name_hint.to_string()
+                } else {
+                    format!("{}/{}", subpath, name_hint)
+                };
+                device.create(&full_path, is_dir)
             }
         }
-        
-        Ok(current_id)
     }
 
-    fn open_file(&mut self, inode_id: u32, mode: &str) -> Result<u32, String> {
-        if !self.inode_map.contains_key(&inode_id) {
-            return Err("Inode not found".to_string());
+    /// Like `create_at`, but for symlinks: mounted `StorageDevice`s have no
+    /// symlink-capable op, so a parent resolving onto a mount is rejected
+    /// rather than silently falling through to the shadowed root-tree inode.
+    fn create_symlink_at(&mut self, resolved: Resolved, name: String, target: String) -> Result<(), FsError> {
+        match resolved {
+            Resolved::Root(parent_id) => self.create_symlink(parent_id, name, target).map(|_| ()),
+            Resolved::Mounted { .. } => Err(FsError::UnsupportedOperation),
         }
-        
+    }
+
+    /// Like `create_at`, but for device nodes: mounted `StorageDevice`s have
+    /// no device-node op, so a parent resolving onto a mount is rejected
+    /// rather than silently falling through to the shadowed root-tree inode.
+    fn mknod_at(&mut self, resolved: Resolved, name: String, is_block: bool, device_id: u32) -> Result<(), FsError> {
+        match resolved {
+            Resolved::Root(parent_id) => self.mknod(parent_id, name, is_block, device_id).map(|_| ()),
+            Resolved::Mounted { .. } => Err(FsError::UnsupportedOperation),
+        }
+    }
+
+    fn open_file(&mut self, resolved: Resolved, flags: u32) -> Result<u32, FsError> {
+        let target = match resolved {
+            Resolved::Root(inode_id) => {
+                if !self.inode_map.contains_key(&inode_id) {
+                    return Err(FsError::InodeNotFound);
+                }
+                FdTarget::Root(inode_id)
+            }
+            Resolved::Mounted { mount_point, subpath } => {
+                let device = self.mount_table.get(&mount_point).ok_or(FsError::NotFound)?;
+                device.open(&subpath)?;
+                FdTarget::Mounted { mount_point, path: subpath }
+            }
+        };
+
         let fd = self.next_fd;
         self.next_fd += 1;
-        
+
         let descriptor = FileDescriptor {
-            inode_id,
+            target,
             offset: 0,
-            mode: mode.to_string(),
+            flags,
         };
-        
+
         self.open_files.insert(fd, descriptor);
         Ok(fd)
     }
 
-    fn read_file(&mut self, fd: u32, buf_size: usize) -> Result<Vec<u8>, String> {
-        if let Some(descriptor) = self.open_files.get_mut(&fd) {
-            if let Some(inode) = self.inode_map.get(&descriptor.inode_id) {
-                let start = descriptor.offset;
+    /// Resolve `path` honoring `O_CREAT`/`O_EXCL`/`O_TRUNC` before handing
+    /// off to `open_file`: a missing path is created when `O_CREAT` is set,
+    /// an existing one rejected when `O_EXCL` is also set, and an existing
+    /// file's contents cleared when `O_TRUNC` is set.
+    fn open_with_flags(&mut self, path: &str, flags: u32) -> Result<u32, FsError> {
+        let trimmed = path.trim_matches('/');
+
+        let resolved = match self.resolve(trimmed) {
+            Ok(resolved) => {
+                if flags & O_CREAT != 0 && flags & O_EXCL != 0 {
+                    return Err(FsError::AlreadyExists);
+                }
+                resolved
+            }
+            Err(FsError::NotFound) if flags & O_CREAT != 0 => {
+                let (parent_path, name) = match trimmed.rfind('/') {
+                    Some(i) => (&trimmed[..i], trimmed[i + 1..].to_string()),
+                    None => ("", trimmed.to_string()),
+                };
+                if name.is_empty() {
+                    return Err(FsError::InvalidPath);
+                }
+
+                let parent_resolved = self.resolve(parent_path)?;
+                self.create_at(parent_resolved, &name, false)?;
+                self.resolve(trimmed)?
+            }
+            Err(e) => return Err(e),
+        };
+
+        if flags & O_TRUNC != 0 {
+            self.truncate_resolved(&resolved, 0)?;
+        }
+
+        self.open_file(resolved, flags)
+    }
+
+    fn truncate_resolved(&mut self, resolved: &Resolved, len: usize) -> Result<(), FsError> {
+        match resolved {
+            Resolved::Root(inode_id) => {
+                let inode = self.inode_map.get_mut(inode_id).ok_or(FsError::InodeNotFound)?;
+                match inode.inode_type {
+                    InodeType::Directory => return Err(FsError::IsDirectory),
+                    InodeType::CharDevice | InodeType::BlockDevice => return Err(FsError::UnsupportedOperation),
+                    _ => {}
+                }
+                if len > MAX_FILE_SIZE {
+                    return Err(FsError::TooLarge);
+                }
+                inode.data.resize(len, 0);
+                Ok(())
+            }
+            Resolved::Mounted { mount_point, subpath } => {
+                let device = self.mount_table.get_mut(mount_point).ok_or(FsError::NotFound)?;
+                device.truncate(subpath, len)
+            }
+        }
+    }
+
+    fn read_file(&mut self, fd: u32, buf_size: usize) -> Result<Vec<u8>, FsError> {
+        let (target, offset) = {
+            let descriptor = self.open_files.get(&fd).ok_or(FsError::InodeNotFound)?;
+            (descriptor.target.clone(), descriptor.offset)
+        };
+
+        match target {
+            FdTarget::Root(inode_id) => {
+                let inode = self.inode_map.get(&inode_id).ok_or(FsError::InodeNotFound)?;
+                if matches!(inode.inode_type, InodeType::CharDevice | InodeType::BlockDevice) {
+                    let device_id = device_id_from_data(&inode.data)?;
+                    return self.read_device(device_id, buf_size);
+                }
+
+                let start = std::cmp::min(offset, inode.data.len());
                 let end = std::cmp::min(start + buf_size, inode.data.len());
                 let read_data = inode.data[start..end].to_vec();
-                descriptor.offset = end;
+                self.open_files.get_mut(&fd).unwrap().offset = end;
                 Ok(read_data)
-            } else {
-                Err("Inode not found".to_string())
             }
-        } else {
-            Err("File descriptor not found".to_string())
+            FdTarget::Mounted { mount_point, path } => {
+                let device = self.mount_table.get(&mount_point).ok_or(FsError::NotFound)?;
+                let data = device.read(&path, offset, buf_size)?;
+                self.open_files.get_mut(&fd).unwrap().offset += data.len();
+                Ok(data)
+            }
         }
     }
 
-    fn write_file(&mut self, fd: u32, data: &[u8]) -> Result<usize, String> {
-        if let Some(descriptor) = self.open_files.get_mut(&fd) {
-            if let Some(inode) = self.inode_map.get_mut(&descriptor.inode_id) {
-                let written = data.len();
-                inode.data.extend_from_slice(data);
+    /// Write at the descriptor's current offset, overwriting in place and
+    /// extending the file as needed, rather than always appending. `O_APPEND`
+    /// forces the write to the current end of the file regardless of where
+    /// the offset points.
+    fn write_file(&mut self, fd: u32, data: &[u8]) -> Result<usize, FsError> {
+        let (target, offset, append) = {
+            let descriptor = self.open_files.get(&fd).ok_or(FsError::InodeNotFound)?;
+            (descriptor.target.clone(), descriptor.offset, descriptor.flags & O_APPEND != 0)
+        };
+
+        match target {
+            FdTarget::Root(inode_id) => {
+                let inode = self.inode_map.get_mut(&inode_id).ok_or(FsError::InodeNotFound)?;
+                if matches!(inode.inode_type, InodeType::CharDevice | InodeType::BlockDevice) {
+                    let device_id = device_id_from_data(&inode.data)?;
+                    return self.write_device(device_id, data);
+                }
+                if matches!(inode.inode_type, InodeType::Directory) {
+                    return Err(FsError::IsDirectory);
+                }
+
+                let write_at = if append { inode.data.len() } else { offset };
+                let end = write_at.checked_add(data.len()).filter(|&end| end <= MAX_FILE_SIZE).ok_or(FsError::TooLarge)?;
+                if inode.data.len() < end {
+                    inode.data.resize(end, 0);
+                }
+                inode.data[write_at..end].copy_from_slice(data);
+                self.open_files.get_mut(&fd).unwrap().offset = end;
+                Ok(data.len())
+            }
+            FdTarget::Mounted { mount_point, path } => {
+                let write_at = if append {
+                    self.mount_table.get(&mount_point).ok_or(FsError::NotFound)?.stat(&path)?.size
+                } else {
+                    offset
+                };
+
+                let device = self.mount_table.get_mut(&mount_point).ok_or(FsError::NotFound)?;
+                let written = device.write(&path, write_at, data)?;
+                self.open_files.get_mut(&fd).unwrap().offset = write_at + written;
                 Ok(written)
-            } else {
-                Err("Inode not found".to_string())
             }
-        } else {
-            Err("File descriptor not found".to_string())
         }
     }
 
-    fn close_file(&mut self, fd: u32) -> Result<(), String> {
+    /// Reposition the descriptor's offset per POSIX `lseek(2)` semantics.
+    fn seek_fd(&mut self, fd: u32, offset: i64, whence: u32) -> Result<usize, FsError> {
+        let (target, current_offset) = {
+            let descriptor = self.open_files.get(&fd).ok_or(FsError::InodeNotFound)?;
+            (descriptor.target.clone(), descriptor.offset)
+        };
+
+        let size = match &target {
+            FdTarget::Root(inode_id) => self.inode_map.get(inode_id).ok_or(FsError::InodeNotFound)?.data.len(),
+            FdTarget::Mounted { mount_point, path } => {
+                self.mount_table.get(mount_point).ok_or(FsError::NotFound)?.stat(path)?.size
+            }
+        };
+
+        let base: i64 = match whence {
+            SEEK_SET => 0,
+            SEEK_CUR => current_offset as i64,
+            SEEK_END => size as i64,
+            _ => return Err(FsError::InvalidPath),
+        };
+
+        let new_offset = base.checked_add(offset).ok_or(FsError::InvalidPath)?;
+        if new_offset < 0 {
+            return Err(FsError::InvalidPath);
+        }
+
+        self.open_files.get_mut(&fd).unwrap().offset = new_offset as usize;
+        Ok(new_offset as usize)
+    }
+
+    /// Truncate the file behind `fd` to `len` bytes, independent of the
+    /// descriptor's current offset.
+    fn truncate_fd(&mut self, fd: u32, len: usize) -> Result<(), FsError> {
+        let target = self.open_files.get(&fd).ok_or(FsError::InodeNotFound)?.target.clone();
+        match target {
+            FdTarget::Root(inode_id) => {
+                let inode = self.inode_map.get_mut(&inode_id).ok_or(FsError::InodeNotFound)?;
+                match inode.inode_type {
+                    InodeType::Directory => return Err(FsError::IsDirectory),
+                    InodeType::CharDevice | InodeType::BlockDevice => return Err(FsError::UnsupportedOperation),
+                    _ => {}
+                }
+                if len > MAX_FILE_SIZE {
+                    return Err(FsError::TooLarge);
+                }
+                inode.data.resize(len, 0);
+                Ok(())
+            }
+            FdTarget::Mounted { mount_point, path } => {
+                let device = self.mount_table.get_mut(&mount_point).ok_or(FsError::NotFound)?;
+                device.truncate(&path, len)
+            }
+        }
+    }
+
+    fn close_file(&mut self, fd: u32) -> Result<(), FsError> {
         if self.open_files.remove(&fd).is_some() {
             Ok(())
         } else {
-            Err("File descriptor not found".to_string())
+            Err(FsError::InodeNotFound)
         }
     }
 
-    fn list_directory(&self, inode_id: u32) -> Result<Vec<String>, String> {
-        if let Some(inode) = self.inode_map.get(&inode_id) {
-            if matches!(inode.inode_type, InodeType::Directory) {
-                let entries: Vec<String> = inode.children.keys().cloned().collect();
-                Ok(entries)
-            } else {
-                Err("Not a directory".to_string())
+    fn list_directory(&self, resolved: &Resolved) -> Result<Vec<String>, FsError> {
+        match resolved {
+            Resolved::Root(inode_id) => {
+                let inode = self.inode_map.get(inode_id).ok_or(FsError::InodeNotFound)?;
+                if matches!(inode.inode_type, InodeType::Directory) {
+                    Ok(inode.children.keys().cloned().collect())
+                } else {
+                    Err(FsError::NotADirectory)
+                }
+            }
+            Resolved::Mounted { mount_point, subpath } => {
+                let device = self.mount_table.get(mount_point).ok_or(FsError::NotFound)?;
+                device.list(subpath)
             }
-        } else {
-            Err("Inode not found".to_string())
         }
     }
 
-    fn read_file_content(&self, inode_id: u32) -> Result<String, String> {
-        if let Some(inode) = self.inode_map.get(&inode_id) {
-            match String::from_utf8(inode.data.clone()) {
-                Ok(s) => Ok(s),
-                Err(_) => Err("File content is not valid UTF-8".to_string()),
+    fn read_file_content(&self, resolved: &Resolved) -> Result<String, FsError> {
+        match resolved {
+            Resolved::Root(inode_id) => {
+                let inode = self.inode_map.get(inode_id).ok_or(FsError::InodeNotFound)?;
+                if matches!(inode.inode_type, InodeType::Directory) {
+                    return Err(FsError::IsDirectory);
+                }
+                String::from_utf8(inode.data.clone()).map_err(|_| FsError::InvalidPath)
             }
-        } else {
-            Err("Inode not found".to_string())
+            Resolved::Mounted { mount_point, subpath } => {
+                let device = self.mount_table.get(mount_point).ok_or(FsError::NotFound)?;
+                let stat = device.stat(subpath)?;
+                if stat.is_dir {
+                    return Err(FsError::IsDirectory);
+                }
+                let data = device.read(subpath, 0, stat.size)?;
+                String::from_utf8(data).map_err(|_| FsError::InvalidPath)
+            }
+        }
+    }
+
+    /// Serialize `inode_map`, `next_inode_id` and `root_inode_id` into a
+    /// compact blob the host can stash in localStorage/IndexedDB and later
+    /// hand back to `restore`. Mounted devices and open file descriptors are
+    /// not part of the snapshot.
+    fn snapshot(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_u32(&mut buf, self.next_inode_id);
+        write_u32(&mut buf, self.root_inode_id);
+        write_u32(&mut buf, self.inode_map.len() as u32);
+
+        for inode in self.inode_map.values() {
+            write_u32(&mut buf, inode.id);
+            buf.push(inode_type_tag(&inode.inode_type));
+            write_bytes(&mut buf, inode.name.as_bytes());
+            write_u32(&mut buf, inode.parent.unwrap_or(NO_PARENT));
+            write_bytes(&mut buf, &inode.data);
+            write_u32(&mut buf, inode.children.len() as u32);
+            for (name, &child_id) in &inode.children {
+                write_bytes(&mut buf, name.as_bytes());
+                write_u32(&mut buf, child_id);
+            }
+        }
+
+        buf
+    }
+
+    /// Restore a blob produced by `snapshot`, validating that every child
+    /// and parent reference resolves to a present inode before committing
+    /// it, so a corrupt blob is rejected rather than leaving the kernel in a
+    /// half-restored state.
+    fn restore(&mut self, blob: &[u8]) -> Result<(), FsError> {
+        let mut pos = 0usize;
+        let next_inode_id = read_u32(blob, &mut pos)?;
+        let root_inode_id = read_u32(blob, &mut pos)?;
+        let inode_count = read_u32(blob, &mut pos)?;
+
+        let mut inode_map = HashMap::new();
+        for _ in 0..inode_count {
+            let id = read_u32(blob, &mut pos)?;
+            let inode_type = inode_type_from_tag(read_u8(blob, &mut pos)?)?;
+            let name = read_string(blob, &mut pos)?;
+            let parent_raw = read_u32(blob, &mut pos)?;
+            let parent = if parent_raw == NO_PARENT { None } else { Some(parent_raw) };
+            let data = read_bytes(blob, &mut pos)?;
+
+            let child_count = read_u32(blob, &mut pos)?;
+            let mut children = HashMap::new();
+            for _ in 0..child_count {
+                let child_name = read_string(blob, &mut pos)?;
+                let child_id = read_u32(blob, &mut pos)?;
+                children.insert(child_name, child_id);
+            }
+
+            inode_map.insert(id, Inode { id, inode_type, name, data, children, parent });
+        }
+
+        if !inode_map.contains_key(&root_inode_id) {
+            return Err(FsError::Corrupt);
         }
+        for inode in inode_map.values() {
+            if let Some(parent_id) = inode.parent {
+                if !inode_map.contains_key(&parent_id) {
+                    return Err(FsError::Corrupt);
+                }
+            }
+            for &child_id in inode.children.values() {
+                if !inode_map.contains_key(&child_id) {
+                    return Err(FsError::Corrupt);
+                }
+            }
+        }
+
+        // `next_inode_id` must be past every id actually present, or the next
+        // create_inode call would reuse one and clobber an existing inode.
+        if let Some(&max_id) = inode_map.keys().max() {
+            if next_inode_id <= max_id {
+                return Err(FsError::Corrupt);
+            }
+        }
+
+        self.inode_map = inode_map;
+        self.next_inode_id = next_inode_id;
+        self.root_inode_id = root_inode_id;
+        self.open_files.clear();
+        Ok(())
     }
 }
 
@@ -291,6 +1183,27 @@ impl Kernel {
 // WASM-BINDGEN EXPORTS & SYSCALLS
 // ============================================================================
 
+/// Split a path into its parent directory and final component, rejecting an
+/// empty path (root) or an empty final component. Shared by the syscalls
+/// that create something at a path: `fs_create`, `fs_symlink`, `fs_mknod`.
+fn split_parent_and_name(path: &str) -> Result<(&str, String), FsError> {
+    let trimmed = path.trim_matches('/');
+    if trimmed.is_empty() {
+        return Err(FsError::InvalidPath);  // Cannot create root or with empty name
+    }
+
+    let (parent_path, name) = match trimmed.rfind('/') {
+        Some(last_slash) => (&trimmed[..last_slash], trimmed[last_slash + 1..].to_string()),
+        None => ("", trimmed.to_string()),
+    };
+
+    if name.is_empty() {
+        return Err(FsError::InvalidPath);
+    }
+
+    Ok((parent_path, name))
+}
+
 #[wasm_bindgen]
 pub fn boot(current_time_ms: u64) -> String {
     KERNEL.with(|k| {
@@ -298,13 +1211,13 @@ pub fn boot(current_time_ms: u64) -> String {
         kernel.booted = true;
         kernel.start_time_ms = current_time_ms;
         kernel.current_time_ms = current_time_ms;
-        
+
         // Create initial filesystem structure
         let _ = kernel.create_inode(0, "bin".to_string(), InodeType::Directory);
         let _ = kernel.create_inode(0, "etc".to_string(), InodeType::Directory);
         let _ = kernel.create_inode(0, "home".to_string(), InodeType::Directory);
         let _ = kernel.create_inode(0, "tmp".to_string(), InodeType::Directory);
-        
+
         "BrowserOS v0.2 (WASM-based virtual OS)\nType 'help' for command list.\n".to_string()
     })
 }
@@ -317,24 +1230,90 @@ pub fn update_time(current_time_ms: u64) {
     });
 }
 
-// Syscall: fs_open(path, mode) -> fd
+// Syscall: fs_open(path, flags) -> fd, or a negative errno on error.
+// `flags` is a bitmask of O_RDONLY/O_WRONLY/O_RDWR/O_CREAT/O_EXCL/O_TRUNC/O_APPEND.
 #[wasm_bindgen]
-pub fn fs_open(path: &str, mode: &str) -> i32 {
+pub fn fs_open(path: &str, flags: u32) -> i32 {
     KERNEL.with(|k| {
         let mut kernel = k.borrow_mut();
-        match kernel.get_inode(path) {
-            Ok(inode_id) => {
-                match kernel.open_file(inode_id, mode) {
-                    Ok(fd) => fd as i32,
-                    Err(_) => -1,
-                }
-            }
-            Err(_) => -1,
+        match kernel.open_with_flags(path, flags) {
+            Ok(fd) => fd as i32,
+            Err(e) => e.errno(),
+        }
+    })
+}
+
+// Syscall: fs_seek(fd, offset, whence) -> new offset, or negative errno.
+// `whence` is one of SEEK_SET/SEEK_CUR/SEEK_END.
+#[wasm_bindgen]
+pub fn fs_seek(fd: u32, offset: i64, whence: u32) -> i64 {
+    KERNEL.with(|k| {
+        let mut kernel = k.borrow_mut();
+        match kernel.seek_fd(fd, offset, whence) {
+            Ok(new_offset) => new_offset as i64,
+            Err(e) => e.errno() as i64,
+        }
+    })
+}
+
+// Syscall: fs_truncate(fd, len) -> 0 on success, negative errno on error
+#[wasm_bindgen]
+pub fn fs_truncate(fd: u32, len: usize) -> i32 {
+    KERNEL.with(|k| {
+        let mut kernel = k.borrow_mut();
+        match kernel.truncate_fd(fd, len) {
+            Ok(_) => 0,
+            Err(e) => e.errno(),
+        }
+    })
+}
+
+// Syscall: fs_mount(path, device_kind) -> 0 on success, negative errno on error
+#[wasm_bindgen]
+pub fn fs_mount(path: &str, device_kind: &str) -> i32 {
+    KERNEL.with(|k| {
+        let mut kernel = k.borrow_mut();
+        match kernel.mount(path, device_kind) {
+            Ok(_) => 0,
+            Err(e) => e.errno(),
+        }
+    })
+}
+
+// Syscall: fs_unmount(path) -> 0 on success, negative errno on error
+#[wasm_bindgen]
+pub fn fs_unmount(path: &str) -> i32 {
+    KERNEL.with(|k| {
+        let mut kernel = k.borrow_mut();
+        match kernel.unmount(path) {
+            Ok(_) => 0,
+            Err(e) => e.errno(),
+        }
+    })
+}
+
+// Syscall: fs_snapshot() -> serialized inode tree blob
+#[wasm_bindgen]
+pub fn fs_snapshot() -> Vec<u8> {
+    KERNEL.with(|k| {
+        let kernel = k.borrow();
+        kernel.snapshot()
+    })
+}
+
+// Syscall: fs_restore(blob) -> 0 on success, negative errno on a corrupt blob
+#[wasm_bindgen]
+pub fn fs_restore(blob: Vec<u8>) -> i32 {
+    KERNEL.with(|k| {
+        let mut kernel = k.borrow_mut();
+        match kernel.restore(&blob) {
+            Ok(_) => 0,
+            Err(e) => e.errno(),
         }
     })
 }
 
-// Syscall: fs_read(fd, size) -> data as comma-separated bytes
+// Syscall: fs_read(fd, size) -> data as comma-separated bytes, empty on error
 #[wasm_bindgen]
 pub fn fs_read(fd: u32, size: usize) -> String {
     KERNEL.with(|k| {
@@ -351,7 +1330,7 @@ pub fn fs_read(fd: u32, size: usize) -> String {
     })
 }
 
-// Syscall: fs_write(fd, data as comma-separated bytes) -> bytes_written
+// Syscall: fs_write(fd, data as comma-separated bytes) -> bytes_written, or negative errno
 #[wasm_bindgen]
 pub fn fs_write(fd: u32, data: &str) -> i32 {
     KERNEL.with(|k| {
@@ -361,122 +1340,146 @@ pub fn fs_write(fd: u32, data: &str) -> i32 {
             .filter(|s| !s.is_empty())
             .map(|s| s.trim().parse::<u8>())
             .collect();
-        
+
         match bytes {
             Ok(data) => match kernel.write_file(fd, &data) {
                 Ok(written) => written as i32,
-                Err(_) => -1,
+                Err(e) => e.errno(),
             },
-            Err(_) => -1,
+            Err(_) => EINVAL,
         }
     })
 }
 
-// Syscall: fs_create(path, type) -> 0 on success, -1 on error
+// Syscall: fs_create(path, type) -> 0 on success, negative errno on error
 #[wasm_bindgen]
 pub fn fs_create(path: &str, is_dir: bool) -> i32 {
     KERNEL.with(|k| {
         let mut kernel = k.borrow_mut();
-        
-        // Validate path
-        let path = path.trim_matches('/');
-        if path.is_empty() {
-            return -1;  // Cannot create root or with empty name
-        }
-        
-        if let Some(last_slash) = path.rfind('/') {
-            let parent_path = &path[..last_slash];
-            let name = path[last_slash + 1..].to_string();
-            
-            // Validate name is not empty
-            if name.is_empty() {
-                return -1;
-            }
-            
-            if let Ok(parent_id) = kernel.get_inode(parent_path) {
-                let inode_type = if is_dir { InodeType::Directory } else { InodeType::File };
-                match kernel.create_inode(parent_id, name, inode_type) {
-                    Ok(_) => 0,
-                    Err(_) => -1,
-                }
-            } else {
-                -1
-            }
-        } else {
-            // File in root
-            let inode_type = if is_dir { InodeType::Directory } else { InodeType::File };
-            match kernel.create_inode(0, path.to_string(), inode_type) {
-                Ok(_) => 0,
-                Err(_) => -1,
-            }
+
+        let (parent_path, name) = match split_parent_and_name(path) {
+            Ok(v) => v,
+            Err(e) => return e.errno(),
+        };
+
+        match kernel.resolve(parent_path).and_then(|parent_resolved| kernel.create_at(parent_resolved, &name, is_dir)) {
+            Ok(_) => 0,
+            Err(e) => e.errno(),
+        }
+    })
+}
+
+// Syscall: fs_symlink(target, linkpath) -> 0 on success, negative errno on error
+#[wasm_bindgen]
+pub fn fs_symlink(target: &str, linkpath: &str) -> i32 {
+    KERNEL.with(|k| {
+        let mut kernel = k.borrow_mut();
+
+        let (parent_path, name) = match split_parent_and_name(linkpath) {
+            Ok(v) => v,
+            Err(e) => return e.errno(),
+        };
+
+        match kernel.resolve(parent_path).and_then(|parent_resolved| kernel.create_symlink_at(parent_resolved, name, target.to_string())) {
+            Ok(_) => 0,
+            Err(e) => e.errno(),
         }
     })
 }
 
-// Syscall: fs_close(fd) -> 0 on success, -1 on error
+// Syscall: fs_mknod(path, kind, device_id) -> 0 on success, negative errno on error.
+// `kind` is "char" or "block"; reads/writes on the node are routed through the
+// host callback registered for `device_id` via `fs_register_device`.
+#[wasm_bindgen]
+pub fn fs_mknod(path: &str, kind: &str, device_id: u32) -> i32 {
+    KERNEL.with(|k| {
+        let mut kernel = k.borrow_mut();
+
+        let (parent_path, name) = match split_parent_and_name(path) {
+            Ok(v) => v,
+            Err(e) => return e.errno(),
+        };
+
+        let is_block = match kind {
+            "char" => false,
+            "block" => true,
+            _ => return EINVAL,
+        };
+
+        match kernel.resolve(parent_path).and_then(|parent_resolved| kernel.mknod_at(parent_resolved, name, is_block, device_id)) {
+            Ok(_) => 0,
+            Err(e) => e.errno(),
+        }
+    })
+}
+
+// Syscall: fs_register_device(device_id, handler) -> registers the host callback invoked by
+// read_file/write_file for CharDevice/BlockDevice inodes tagged with that device_id.
+#[wasm_bindgen]
+pub fn fs_register_device(device_id: u32, handler: Function) {
+    KERNEL.with(|k| {
+        let mut kernel = k.borrow_mut();
+        kernel.register_device_handler(device_id, handler);
+    });
+}
+
+// Syscall: fs_close(fd) -> 0 on success, negative errno on error
 #[wasm_bindgen]
 pub fn fs_close(fd: u32) -> i32 {
     KERNEL.with(|k| {
         let mut kernel = k.borrow_mut();
         match kernel.close_file(fd) {
             Ok(_) => 0,
-            Err(_) => -1,
+            Err(e) => e.errno(),
         }
     })
 }
 
-// Syscall: fs_list(path) -> comma-separated filenames
+// Syscall: fs_list(path) -> comma-separated filenames, empty on error
 #[wasm_bindgen]
 pub fn fs_list(path: &str) -> String {
     KERNEL.with(|k| {
         let kernel = k.borrow();
-        match kernel.get_inode(path) {
-            Ok(inode_id) => {
-                match kernel.list_directory(inode_id) {
-                    Ok(entries) => entries.join(","),
-                    Err(_) => String::new(),
-                }
-            }
+        match kernel.resolve(path).and_then(|resolved| kernel.list_directory(&resolved)) {
+            Ok(entries) => entries.join(","),
             Err(_) => String::new(),
         }
     })
 }
 
-// Syscall: fs_exists(path) -> 1 if dir exists, 0 if file exists, -1 if not found
+// Syscall: fs_exists(path, no_follow) -> 1 if dir exists, 0 if file exists, negative errno if not found.
+// `no_follow` reports a symlink itself (lstat-style) instead of following it.
 #[wasm_bindgen]
-pub fn fs_exists(path: &str) -> i32 {
+pub fn fs_exists(path: &str, no_follow: bool) -> i32 {
     KERNEL.with(|k| {
         let kernel = k.borrow();
-        match kernel.get_inode(path) {
-            Ok(inode_id) => {
-                if let Some(inode) = kernel.inode_map.get(&inode_id) {
-                    if matches!(inode.inode_type, InodeType::Directory) {
-                        1
-                    } else {
-                        0
-                    }
-                } else {
-                    -1
+        match kernel.resolve_with(path, !no_follow) {
+            Ok(Resolved::Root(inode_id)) => {
+                match kernel.inode_map.get(&inode_id) {
+                    Some(inode) => if matches!(inode.inode_type, InodeType::Directory) { 1 } else { 0 },
+                    None => EBADF,
                 }
             }
-            Err(_) => -1,
+            Ok(Resolved::Mounted { mount_point, subpath }) => {
+                match kernel.mount_table.get(&mount_point).map(|d| d.stat(&subpath)) {
+                    Some(Ok(stat)) => if stat.is_dir { 1 } else { 0 },
+                    Some(Err(e)) => e.errno(),
+                    None => ENOENT,
+                }
+            }
+            Err(e) => e.errno(),
         }
     })
 }
 
-// Syscall: fs_cat(path) -> file content
+// Syscall: fs_cat(path) -> file content, or an "Error: ..." message
 #[wasm_bindgen]
 pub fn fs_cat(path: &str) -> String {
     KERNEL.with(|k| {
         let kernel = k.borrow();
-        match kernel.get_inode(path) {
-            Ok(inode_id) => {
-                match kernel.read_file_content(inode_id) {
-                    Ok(content) => content,
-                    Err(e) => format!("Error: {}", e),
-                }
-            }
-            Err(e) => format!("Error: {}", e),
+        match kernel.resolve(path).and_then(|resolved| kernel.read_file_content(&resolved)) {
+            Ok(content) => content,
+            Err(e) => format!("Error: {}", e.message()),
         }
     })
 }
@@ -542,9 +1545,9 @@ SYSTEM COMMANDS:
 
 PROCESS COMMANDS:
   ps                 - List processes
-  
+
 TIPS:
   - Paths start with / (e.g., /home/user/file.txt)
   - Use '>' for redirection: echo hello > /tmp/test.txt
 ".to_string()
-}
\ No newline at end of file
+}